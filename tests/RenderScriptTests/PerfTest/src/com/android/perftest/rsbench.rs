@@ -23,13 +23,55 @@
 const int RS_MSG_TEST_DONE = 100;
 const int RS_MSG_RESULTS_READY = 101;
 
-const int gMaxModes = 30;
+const int gMaxModes = 49;
 int gMaxLoops;
 
+// Per-mode iteration counts derived by calibrateLoopCount(), so cheap and
+// expensive tests each run for roughly the same wall-clock budget. A zero
+// entry means the mode has not been calibrated yet.
+static int gCalibratedLoops[gMaxModes];
+
+// Runtime configuration, set by the Java client so the same script can act
+// as either a targeted micro-benchmark or a full sweep without recompiling.
+//
+// List of test indices to run, in order; an empty (zero-length) allocation
+// means "run every mode in testNames[] order", the historical behavior.
+rs_allocation gModeSelection;
+// Whether to force a vsync-aligned present after each mode's results are
+// drawn, trading throughput for a result thumbnail that never tears.
+int gVsyncAligned = 0;
+// Whether drawOffscreenResultScaled composites preserve the offscreen target's
+// aspect ratio (letterboxed) instead of stretching it to fill the display.
+// The small per-frame drawOffscreenResult tiles are always stretched.
+int gLetterboxResult = 0;
+// When >= 0, set by the client in response to a touch/key selection on the
+// HUD, repeatedly benchmarks only this mode instead of advancing through
+// the sweep, so a developer can drill into a single regression live.
+int gManualMode = -1;
+
 // Allocation to send test names back to java
 char *gStringBuffer = 0;
+
+// Per-test timing summary. Frame times are measured in milliseconds.
+// The percentiles are estimated from a small fixed-size ring of the most
+// recent per-frame samples, insertion-sorted once the mode finishes.
+typedef struct TestStats_s {
+    float mean;
+    float stddev;
+    float min;
+    float max;
+    float p50;
+    float p90;
+    float p99;
+} TestStats;
+
 // Allocation to write the results into
-static float gResultBuffer[gMaxModes];
+static TestStats gResultBuffer[gMaxModes];
+
+// Matches CALIBRATION_MAX_LOOPS below, the largest per-test loop count
+// calibrateLoopCount() can produce, so every timed sample is retained for
+// trimming and percentile estimation rather than an approximate window.
+#define PERCENTILE_RING_SIZE 500
 
 rs_program_vertex gProgVertex;
 rs_program_fragment gProgFragmentColor;
@@ -54,6 +96,12 @@ ListAllocs *gTexList100;
 ListAllocs *gSampleTextList100;
 ListAllocs *gListViewText;
 
+// Allocations whose pixel contents are rewritten every frame to benchmark
+// the upload/bandwidth cost of streaming texture updates (camera/video/UI
+// style workloads), as opposed to the once-at-init textures above.
+ListAllocs *gTexStreamSmall;
+ListAllocs *gTexStreamLarge;
+
 rs_mesh g10by10Mesh;
 rs_mesh g100by100Mesh;
 rs_mesh gWbyHMesh;
@@ -65,9 +113,17 @@ rs_font gFontSerif;
 
 int gDisplayMode;
 
+// Number of untimed iterations run before the timed loop, to let caches,
+// shaders and the offscreen target warm up before we start measuring.
+int gWarmupFrames = 5;
+// Fraction (0..1) of the slowest timed samples to discard as outliers
+// before computing the reported mean/stddev, i.e. a trimmed mean.
+float gTrimFraction = 0.1f;
+
 rs_sampler gLinearClamp;
 rs_sampler gLinearWrap;
 rs_sampler gMipLinearWrap;
+rs_sampler gMipNearestWrap;
 rs_sampler gNearestClamp;
 
 rs_program_raster gCullBack;
@@ -91,6 +147,28 @@ rs_program_fragment gProgFragmentMultitex;
 
 rs_allocation gRenderBufferColor;
 rs_allocation gRenderBufferDepth;
+// Downscaled offscreen target used to measure the cost of rendering at a
+// reduced resolution and upscaling the result back to the display
+rs_allocation gRenderBufferColorSmall;
+rs_allocation gRenderBufferDepthSmall;
+// Same-size offscreen color targets in alternate pixel formats, so a given
+// scene's fill-rate/bandwidth cost can be compared across formats. All three
+// share gRenderBufferDepth since only the color attachment format varies.
+rs_allocation gRenderBufferColor565;
+rs_allocation gRenderBufferColor8;
+
+// Tracks whichever color target the current (or just-finished) mode actually
+// rendered its result into, since several modes render to an alternate
+// offscreen target (gRenderBufferColorSmall/565/8) instead of the default
+// gRenderBufferColor. The thumbnail/preview draws in root() and drawHud()
+// read this instead of hardcoding gRenderBufferColor, so they show the
+// running mode's real output rather than stale content from a prior mode.
+static rs_allocation gActiveColorTarget;
+// Whether gActiveColorTarget is safe to sample through gProgFragmentTexture's
+// RGBA path. RGB_565 and the 8-bit single-channel targets aren't guaranteed
+// sampleable that way on every GPU, so the thumbnail/preview draws must skip
+// them rather than bind and sample them every timed frame.
+static bool gActiveColorTargetCompositable;
 
 float gDt = 0;
 
@@ -113,6 +191,15 @@ static float textColors[] = {1.0f, 1.0f, 1.0f, 1.0f,
 static void setupOffscreenTarget() {
     rsgBindColorTarget(gRenderBufferColor, 0);
     rsgBindDepthTarget(gRenderBufferDepth);
+    gActiveColorTarget = gRenderBufferColor;
+    gActiveColorTargetCompositable = true;
+}
+
+static void setupOffscreenTargetSmall() {
+    rsgBindColorTarget(gRenderBufferColorSmall, 0);
+    rsgBindDepthTarget(gRenderBufferDepthSmall);
+    gActiveColorTarget = gRenderBufferColorSmall;
+    gActiveColorTargetCompositable = true;
 }
 
 static void displayFontSamples(int fillNum) {
@@ -186,6 +273,49 @@ static void displaySingletexFill(bool blend, int quadCount) {
     }
 }
 
+// Draws a grid of heavily minified textured quads with the bound sampler set
+// according to filterMode, to isolate the cost of the various min-filter
+// modes (the texture is expected to carry a full mip chain).
+// filterMode: 0 = NEAREST, 1 = LINEAR, 2 = LINEAR_MIP_LINEAR, 3 = LINEAR_MIP_NEAREST
+static void displayTexFilterSamples(int filterMode, int quadCount) {
+    bindProgramVertexOrtho();
+    rs_matrix4x4 matrix;
+    rsMatrixLoadIdentity(&matrix);
+    rsgProgramVertexLoadModelMatrix(&matrix);
+
+    rsgBindProgramStore(gProgStoreBlendNone);
+    rsgBindProgramFragment(gProgFragmentTexture);
+
+    rs_sampler sampler;
+    switch (filterMode) {
+    case 0:
+        sampler = gNearestClamp;
+        break;
+    case 1:
+        sampler = gLinearClamp;
+        break;
+    case 2:
+        sampler = gMipLinearWrap;
+        break;
+    default:
+        sampler = gMipNearestWrap;
+        break;
+    }
+    rsgBindSampler(gProgFragmentTexture, 0, sampler);
+    rsgBindTexture(gProgFragmentTexture, 0, gTexTorus);
+
+    // Quads are drawn tiny on screen so the minification filter dominates
+    float quadSize = 8.0f;
+    for (int i = 0; i < quadCount; i ++) {
+        float startX = (float)((i * 13) % gRenderSurfaceW);
+        float startY = (float)((i * 7) % gRenderSurfaceH);
+        rsgDrawQuadTexCoords(startX, startY, 0, 0, 0,
+                             startX, startY + quadSize, 0, 0, 1,
+                             startX + quadSize, startY + quadSize, 0, 1, 1,
+                             startX + quadSize, startY, 0, 1, 0);
+    }
+}
+
 static void displayMeshSamples(int meshNum) {
 
     bindProgramVertexOrtho();
@@ -304,6 +434,135 @@ static void displayImageWithText(int wResolution, int hResolution, int meshMode)
     }
 }
 
+#define WRAP_LINE_MAX_CHARS 256
+
+// Measures the pixel width of the first len characters of s by copying them
+// into a scratch null-terminated buffer, since rsgMeasureText only accepts
+// whole C strings.
+static int measureTextWidthN(const char *s, int len) {
+    char buf[WRAP_LINE_MAX_CHARS];
+    int n = (len < WRAP_LINE_MAX_CHARS - 1) ? len : WRAP_LINE_MAX_CHARS - 1;
+    for (int i = 0; i < n; i ++) {
+        buf[i] = s[i];
+    }
+    buf[n] = '\0';
+    int left = 0, right = 0, top = 0, bottom = 0;
+    rsgMeasureText(buf, &left, &right, &top, &bottom);
+    return right - left;
+}
+
+// Greedily word-wraps text into lines no wider than rectWidth, drawing each
+// line at (x, advancing baseline) if draw is true. A single word wider than
+// rectWidth is broken up character by character so it never overruns. Lines
+// stop being added once the laid-out height would exceed rectHeight (a
+// rectHeight <= 0 means unbounded). Returns the total laid-out height.
+static int layoutWrappedText(const char *text, int x, int y, int rectWidth, int rectHeight, bool draw) {
+    int left = 0, right = 0, top = 0, bottom = 0;
+    rsgMeasureText(text, &left, &right, &top, &bottom);
+    int lineHeight = top - bottom;
+    if (lineHeight <= 0) {
+        lineHeight = 16;
+    }
+
+    char lineBuf[WRAP_LINE_MAX_CHARS];
+    char candidate[WRAP_LINE_MAX_CHARS];
+    int lineLen = 0;
+    int baseline = y + top;
+    int totalHeight = 0;
+    int i = 0;
+
+    while (text[i] != '\0') {
+        while (text[i] == ' ') {
+            i ++;
+        }
+        if (text[i] == '\0') {
+            break;
+        }
+        if (rectHeight > 0 && totalHeight + lineHeight > rectHeight) {
+            break;
+        }
+
+        int wordStart = i;
+        while (text[i] != ' ' && text[i] != '\0') {
+            i ++;
+        }
+        int wordLen = i - wordStart;
+        if (wordLen > WRAP_LINE_MAX_CHARS - 1) {
+            wordLen = WRAP_LINE_MAX_CHARS - 1;
+        }
+
+        // A token wider than the whole rect: fall back to character breaking
+        if (lineLen == 0 && measureTextWidthN(text + wordStart, wordLen) > rectWidth) {
+            int charsFit = 1;
+            while (charsFit < wordLen && measureTextWidthN(text + wordStart, charsFit + 1) <= rectWidth) {
+                charsFit ++;
+            }
+            if (draw) {
+                for (int c = 0; c < charsFit; c ++) {
+                    lineBuf[c] = text[wordStart + c];
+                }
+                lineBuf[charsFit] = '\0';
+                rsgDrawText(lineBuf, x, baseline);
+            }
+            baseline += lineHeight;
+            totalHeight += lineHeight;
+            i = wordStart + charsFit;
+            lineLen = 0;
+            continue;
+        }
+
+        // Build the candidate line (existing content + separator + word) and
+        // see if it still fits; otherwise flush the current line first. If
+        // the combined line wouldn't even fit in the buffer, skip building it
+        // and treat it the same as not fitting rectWidth (wordLen alone is
+        // already clamped above, so this can only trip when lineLen > 0).
+        int neededLen = lineLen + (lineLen > 0 ? 1 : 0) + wordLen;
+        bool candidateOverflows = neededLen > WRAP_LINE_MAX_CHARS - 1;
+
+        int candidateLen = 0;
+        if (!candidateOverflows) {
+            for (int c = 0; c < lineLen; c ++) {
+                candidate[candidateLen ++] = lineBuf[c];
+            }
+            if (lineLen > 0) {
+                candidate[candidateLen ++] = ' ';
+            }
+            for (int c = 0; c < wordLen; c ++) {
+                candidate[candidateLen ++] = text[wordStart + c];
+            }
+            candidate[candidateLen] = '\0';
+        }
+
+        if (lineLen > 0 && (candidateOverflows || measureTextWidthN(candidate, candidateLen) > rectWidth)) {
+            if (draw) {
+                lineBuf[lineLen] = '\0';
+                rsgDrawText(lineBuf, x, baseline);
+            }
+            baseline += lineHeight;
+            totalHeight += lineHeight;
+            lineLen = 0;
+            // Re-lay the word out at the start of the fresh line, so one wider
+            // than rectWidth takes the character-breaking path above.
+            i = wordStart;
+        } else {
+            lineLen = candidateLen;
+            for (int c = 0; c < candidateLen; c ++) {
+                lineBuf[c] = candidate[c];
+            }
+        }
+    }
+
+    if (lineLen > 0 && (rectHeight <= 0 || totalHeight + lineHeight <= rectHeight)) {
+        if (draw) {
+            lineBuf[lineLen] = '\0';
+            rsgDrawText(lineBuf, x, baseline);
+        }
+        totalHeight += lineHeight;
+    }
+
+    return totalHeight;
+}
+
 // Display a list of text as the list view
 static void displayListView() {
     // set text color
@@ -541,6 +800,58 @@ static void displayPixelLightSamples(int numMeshes, bool heavyVertex) {
     drawToruses(numMeshes, &gVSConstPixel->model, gVSConstPixel);
 }
 
+static int gStreamFrame = 0;
+
+// Procedurally rewrites every pixel of alloc and forces a re-upload, so the
+// draw below pays the cost of a fresh GPU texture upload instead of reusing
+// cached contents from a previous frame.
+static void updateStreamingTexture(rs_allocation alloc) {
+    int w = rsAllocationGetDimX(alloc);
+    int h = rsAllocationGetDimY(alloc);
+    for (int y = 0; y < h; y ++) {
+        for (int x = 0; x < w; x ++) {
+            uchar4 color;
+            color.r = (uchar)((x + gStreamFrame) & 0xff);
+            color.g = (uchar)((y + gStreamFrame) & 0xff);
+            color.b = (uchar)((x + y + gStreamFrame) & 0xff);
+            color.a = 255;
+            rsSetElementAt_uchar4(alloc, color, x, y);
+        }
+    }
+    rsgAllocationSyncAll(alloc);
+}
+
+// Rewrites and re-uploads uploadCount textures every frame, then draws each
+// as a textured quad, isolating upload/bandwidth cost from draw cost.
+static void displayTextureStreamSamples(bool useLarge, int uploadCount) {
+    bindProgramVertexOrtho();
+    rs_matrix4x4 matrix;
+    rsMatrixLoadIdentity(&matrix);
+    rsgProgramVertexLoadModelMatrix(&matrix);
+
+    rsgBindProgramStore(gProgStoreBlendNone);
+    rsgBindProgramFragment(gProgFragmentTexture);
+    rsgBindSampler(gProgFragmentTexture, 0, gLinearClamp);
+
+    gStreamFrame ++;
+
+    ListAllocs *texList = useLarge ? gTexStreamLarge : gTexStreamSmall;
+    float size = useLarge ? 256.0f : 64.0f;
+
+    for (int i = 0; i < uploadCount; i ++) {
+        rs_allocation tex = texList[i].item;
+        updateStreamingTexture(tex);
+        rsgBindTexture(gProgFragmentTexture, 0, tex);
+
+        float startX = (float)((i * (int)size) % gRenderSurfaceW);
+        float startY = (float)((i * 17) % gRenderSurfaceH);
+        rsgDrawQuadTexCoords(startX, startY, 0, 0, 0,
+                             startX, startY + size, 0, 0, 1,
+                             startX + size, startY + size, 0, 1, 1,
+                             startX + size, startY, 0, 1, 0);
+    }
+}
+
 static void displayMultitextureSample(bool blend, int quadCount) {
     bindProgramVertexOrtho();
     rs_matrix4x4 matrix;
@@ -602,7 +913,14 @@ static bool checkInit() {
     return true;
 }
 
-static int benchMode = 0;
+// Offscreen-target modes called from runTest(), defined further below.
+static void displayOffscreenTorusSamples(bool downscale, int switchCount);
+static void displayOffscreenFillSamples(bool downscale, int switchCount);
+static void displayOffscreenFormatSamples(int formatIndex);
+
+// Position in the current sweep; indirected through gModeSelection by
+// getModeAt() to obtain the actual testNames[]/runTest() index.
+static int gSweepCursor = 0;
 static int runningLoops = 0;
 static bool sendMsgFlag = false;
 
@@ -637,6 +955,25 @@ static const char *testNames[] = {
     "UI test with image and text display 3 pages",
     "UI test with image and text display 5 pages",
     "UI test with list view",
+    "Offscreen FBO torus same-size 1x switch per frame",
+    "Offscreen FBO torus downscaled 1x switch per frame",
+    "Offscreen FBO torus same-size 4x switch per frame",
+    "Offscreen FBO textured fill downscaled 4x switch per frame",
+    "Minified texture fill nearest filter",
+    "Minified texture fill linear filter",
+    "Minified texture fill linear mip linear filter",
+    "Minified texture fill linear mip nearest filter",
+    "Texture streaming 1 small texture/frame",
+    "Texture streaming 8 small textures/frame",
+    "Texture streaming 1 large texture/frame",
+    "Texture streaming 4 large textures/frame",
+    "Offscreen torus RGBA_8888",
+    "Offscreen torus RGB_565",
+    "Offscreen torus 8bpp single channel",
+    "Offscreen FBO torus downscaled 4x switch per frame",
+    "Offscreen FBO textured fill same-size 1x switch per frame",
+    "Offscreen FBO textured fill same-size 4x switch per frame",
+    "Offscreen FBO textured fill downscaled 1x switch per frame",
 };
 
 void getTestName(int testIndex) {
@@ -744,10 +1081,67 @@ static void runTest(int index) {
     case 29:
         displayListView();
         break;
+    case 30:
+        displayOffscreenTorusSamples(false, 1);
+        break;
+    case 31:
+        displayOffscreenTorusSamples(true, 1);
+        break;
+    case 32:
+        displayOffscreenTorusSamples(false, 4);
+        break;
+    case 33:
+        displayOffscreenFillSamples(true, 4);
+        break;
+    case 34:
+        displayTexFilterSamples(0, 50);
+        break;
+    case 35:
+        displayTexFilterSamples(1, 50);
+        break;
+    case 36:
+        displayTexFilterSamples(2, 50);
+        break;
+    case 37:
+        displayTexFilterSamples(3, 50);
+        break;
+    case 38:
+        displayTextureStreamSamples(false, 1);
+        break;
+    case 39:
+        displayTextureStreamSamples(false, 8);
+        break;
+    case 40:
+        displayTextureStreamSamples(true, 1);
+        break;
+    case 41:
+        displayTextureStreamSamples(true, 4);
+        break;
+    case 42:
+        displayOffscreenFormatSamples(0);
+        break;
+    case 43:
+        displayOffscreenFormatSamples(1);
+        break;
+    case 44:
+        displayOffscreenFormatSamples(2);
+        break;
+    case 45:
+        displayOffscreenTorusSamples(true, 4);
+        break;
+    case 46:
+        displayOffscreenFillSamples(false, 1);
+        break;
+    case 47:
+        displayOffscreenFillSamples(false, 4);
+        break;
+    case 48:
+        displayOffscreenFillSamples(true, 1);
+        break;
     }
 }
 
-static void drawOffscreenResult(int posX, int posY, int width, int height) {
+static void drawOffscreenResultTex(rs_allocation tex, int posX, int posY, int width, int height) {
     bindProgramVertexOrtho();
 
     rs_matrix4x4 matrix;
@@ -757,7 +1151,7 @@ static void drawOffscreenResult(int posX, int posY, int width, int height) {
     rsgBindProgramFragment(gProgFragmentTexture);
 
     rsgBindSampler(gProgFragmentTexture, 0, gLinearClamp);
-    rsgBindTexture(gProgFragmentTexture, 0, gRenderBufferColor);
+    rsgBindTexture(gProgFragmentTexture, 0, tex);
 
     float startX = posX, startY = posY;
     rsgDrawQuadTexCoords(startX, startY, 0, 0, 1,
@@ -766,6 +1160,334 @@ static void drawOffscreenResult(int posX, int posY, int width, int height) {
                          startX + width, startY, 0, 1, 1);
 }
 
+static void drawOffscreenResult(int posX, int posY, int width, int height) {
+    if (!gActiveColorTargetCompositable) {
+        return;
+    }
+    drawOffscreenResultTex(gActiveColorTarget, posX, posY, width, height);
+}
+
+// Like drawOffscreenResultTex(), but fits tex into the (destW, destH) box
+// according to gLetterboxResult: stretch-to-fit (default) or fixed-aspect
+// letterboxing so the result thumbnail isn't distorted when the offscreen
+// target and display differ in aspect ratio.
+static void drawOffscreenResultScaled(rs_allocation tex, int destX, int destY, int destW, int destH) {
+    if (!gLetterboxResult) {
+        drawOffscreenResultTex(tex, destX, destY, destW, destH);
+        return;
+    }
+
+    int texW = rsAllocationGetDimX(tex);
+    int texH = rsAllocationGetDimY(tex);
+    if (texW == 0 || texH == 0 || destW == 0 || destH == 0) {
+        drawOffscreenResultTex(tex, destX, destY, destW, destH);
+        return;
+    }
+
+    float texAspect = (float)texW / (float)texH;
+    float destAspect = (float)destW / (float)destH;
+    int w = destW, h = destH;
+    if (texAspect > destAspect) {
+        h = (int)((float)destW / texAspect);
+    } else {
+        w = (int)((float)destH * texAspect);
+    }
+    int x = destX + (destW - w) / 2;
+    int y = destY + (destH - h) / 2;
+    drawOffscreenResultTex(tex, x, y, w, h);
+}
+
+// Returns the number of modes the current sweep should run: every entry in
+// gModeSelection, or gMaxModes if that allocation is empty (the default).
+static int getModeCount() {
+    int selCount = rsAllocationGetDimX(gModeSelection);
+    return (selCount > 0) ? selCount : gMaxModes;
+}
+
+// Maps a sweep position to an actual testNames[]/runTest() index, indirecting
+// through gModeSelection when the client configured a subset to run.
+static int getModeAt(int cursor) {
+    int selCount = rsAllocationGetDimX(gModeSelection);
+    if (selCount > 0) {
+        return rsGetElementAt_int(gModeSelection, cursor);
+    }
+    return cursor;
+}
+
+// Renders the torus scene into an offscreen color/depth target and composites
+// it back onto the default surface as a full-screen textured quad, switchCount
+// times per frame, so the per-switch FBO bind/resolve overhead can be isolated
+// from the draw cost itself.
+static void displayOffscreenTorusSamples(bool downscale, int switchCount) {
+    rs_allocation colorTarget = downscale ? gRenderBufferColorSmall : gRenderBufferColor;
+
+    for (int s = 0; s < switchCount; s ++) {
+        if (downscale) {
+            setupOffscreenTargetSmall();
+        } else {
+            setupOffscreenTarget();
+        }
+        int targetW = rsAllocationGetDimX(colorTarget);
+        int targetH = rsAllocationGetDimY(colorTarget);
+        rsgClearColor(0.1f, 0.1f, 0.1f, 1.0f);
+        rsgClearDepth(1.0f);
+
+        rsgBindProgramVertex(gProgVertex);
+        rsgBindProgramRaster(gCullBack);
+        rs_matrix4x4 proj;
+        float aspect = (float)targetW / (float)targetH;
+        rsMatrixLoadPerspective(&proj, 30.0f, aspect, 0.1f, 100.0f);
+        rsgProgramVertexLoadProjectionMatrix(&proj);
+
+        rsgBindProgramStore(gProgStoreBlendNoneDepth);
+        rsgBindProgramFragment(gProgFragmentTexture);
+        rsgBindSampler(gProgFragmentTexture, 0, gLinearClamp);
+        rsgBindTexture(gProgFragmentTexture, 0, gTexTorus);
+
+        gTorusRotation += 50.0f * gDt;
+        if (gTorusRotation > 360.0f) {
+            gTorusRotation -= 360.0f;
+        }
+        rs_matrix4x4 matrix;
+        drawToruses(2, &matrix, 0);
+
+        rsgClearAllRenderTargets();
+        int surfaceW = rsgGetWidth();
+        int surfaceH = rsgGetHeight();
+        drawOffscreenResultScaled(colorTarget, 0, 0, surfaceW, surfaceH);
+    }
+}
+
+// Renders a textured full-screen fill into an offscreen color/depth target
+// and composites it back, switchCount times per frame.
+static void displayOffscreenFillSamples(bool downscale, int switchCount) {
+    rs_allocation colorTarget = downscale ? gRenderBufferColorSmall : gRenderBufferColor;
+
+    for (int s = 0; s < switchCount; s ++) {
+        if (downscale) {
+            setupOffscreenTargetSmall();
+        } else {
+            setupOffscreenTarget();
+        }
+        int targetW = rsAllocationGetDimX(colorTarget);
+        int targetH = rsAllocationGetDimY(colorTarget);
+        rsgClearColor(0.1f, 0.1f, 0.1f, 1.0f);
+        rsgClearDepth(1.0f);
+
+        bindProgramVertexOrtho();
+        rs_matrix4x4 matrix;
+        rsMatrixLoadIdentity(&matrix);
+        rsgProgramVertexLoadModelMatrix(&matrix);
+        rsgBindProgramStore(gProgStoreBlendNone);
+        rsgBindProgramFragment(gProgFragmentTexture);
+        rsgBindSampler(gProgFragmentTexture, 0, gLinearClamp);
+        rsgBindTexture(gProgFragmentTexture, 0, gTexOpaque);
+        rsgDrawQuadTexCoords(0, 0, 0, 0, 0,
+                             0, targetH, 0, 0, 1,
+                             targetW, targetH, 0, 1, 1,
+                             targetW, 0, 0, 1, 0);
+
+        rsgClearAllRenderTargets();
+        int surfaceW = rsgGetWidth();
+        int surfaceH = rsgGetHeight();
+        drawOffscreenResultScaled(colorTarget, 0, 0, surfaceW, surfaceH);
+    }
+}
+
+// Renders the torus scene into a same-size offscreen target of the given
+// color format and composites it back, so fill-rate/bandwidth cost can be
+// compared across formats on the same GPU.
+// formatIndex: 0 = RGBA_8888, 1 = RGB_565, 2 = 8-bit single channel
+static void displayOffscreenFormatSamples(int formatIndex) {
+    rs_allocation colorTarget;
+    switch (formatIndex) {
+    case 0:
+        colorTarget = gRenderBufferColor;
+        break;
+    case 1:
+        colorTarget = gRenderBufferColor565;
+        break;
+    default:
+        colorTarget = gRenderBufferColor8;
+        break;
+    }
+
+    rsgBindColorTarget(colorTarget, 0);
+    rsgBindDepthTarget(gRenderBufferDepth);
+    gActiveColorTarget = colorTarget;
+    gActiveColorTargetCompositable = (formatIndex == 0);
+    int targetW = rsAllocationGetDimX(colorTarget);
+    int targetH = rsAllocationGetDimY(colorTarget);
+    rsgClearColor(0.1f, 0.1f, 0.1f, 1.0f);
+    rsgClearDepth(1.0f);
+
+    rsgBindProgramVertex(gProgVertex);
+    rsgBindProgramRaster(gCullBack);
+    rs_matrix4x4 proj;
+    float aspect = (float)targetW / (float)targetH;
+    rsMatrixLoadPerspective(&proj, 30.0f, aspect, 0.1f, 100.0f);
+    rsgProgramVertexLoadProjectionMatrix(&proj);
+
+    rsgBindProgramStore(gProgStoreBlendNoneDepth);
+    rsgBindProgramFragment(gProgFragmentTexture);
+    rsgBindSampler(gProgFragmentTexture, 0, gLinearClamp);
+    rsgBindTexture(gProgFragmentTexture, 0, gTexTorus);
+
+    gTorusRotation += 50.0f * gDt;
+    if (gTorusRotation > 360.0f) {
+        gTorusRotation -= 360.0f;
+    }
+    rs_matrix4x4 matrix;
+    drawToruses(2, &matrix, 0);
+
+    rsgClearAllRenderTargets();
+    int surfaceW = rsgGetWidth();
+    int surfaceH = rsgGetHeight();
+    // RGB_565 and the 8-bit target aren't guaranteed sampleable by
+    // gProgFragmentTexture's RGBA path on every GPU, so only composite the
+    // RGBA_8888 variant back; the others are still fully rendered and timed.
+    if (formatIndex == 0) {
+        drawOffscreenResultScaled(colorTarget, 0, 0, surfaceW, surfaceH);
+    }
+}
+
+// Target wall-clock budget each test's timed loop should take, in ms.
+#define CALIBRATION_TARGET_MS 500.0f
+#define CALIBRATION_PROBE_ITERATIONS 5
+#define CALIBRATION_MIN_LOOPS 10
+#define CALIBRATION_MAX_LOOPS 500
+
+// Runs index's test for a short probe interval to estimate its per-frame
+// cost, then derives an iteration count that would make its timed loop take
+// roughly CALIBRATION_TARGET_MS, clamped to sane bounds.
+static int calibrateLoopCount(int index) {
+    setupOffscreenTarget();
+    gRenderSurfaceW = rsAllocationGetDimX(gRenderBufferColor);
+    gRenderSurfaceH = rsAllocationGetDimY(gRenderBufferColor);
+
+    // Warm up the same as the timed run below before probing, so the
+    // per-frame cost this calibration is based on reflects steady-state
+    // caches/shaders/FBO binds rather than a cold-start estimate.
+    for (int w = 0; w < gWarmupFrames; w ++) {
+        rsgClearColor(0.1f, 0.1f, 0.1f, 1.0f);
+        rsgClearDepth(1.0f);
+        runTest(index);
+    }
+
+    rsgFinish();
+    int64_t start = rsUptimeMillis();
+    for (int p = 0; p < CALIBRATION_PROBE_ITERATIONS; p ++) {
+        rsgClearColor(0.1f, 0.1f, 0.1f, 1.0f);
+        rsgClearDepth(1.0f);
+        runTest(index);
+    }
+    rsgFinish();
+    int64_t end = rsUptimeMillis();
+
+    rsgClearAllRenderTargets();
+    gRenderSurfaceW = rsgGetWidth();
+    gRenderSurfaceH = rsgGetHeight();
+
+    float perFrameMs = (float)(end - start) / (float)CALIBRATION_PROBE_ITERATIONS;
+    if (perFrameMs < 0.001f) {
+        perFrameMs = 0.001f;
+    }
+
+    int loops = (int)(CALIBRATION_TARGET_MS / perFrameMs);
+    if (loops < CALIBRATION_MIN_LOOPS) {
+        loops = CALIBRATION_MIN_LOOPS;
+    }
+    if (loops > CALIBRATION_MAX_LOOPS) {
+        loops = CALIBRATION_MAX_LOOPS;
+    }
+    return loops;
+}
+
+// A tiny immediate-mode overlay layer the live HUD is built from: a filled
+// rect, a blitted textured quad, drawn text, and a non-drawing text measure.
+// Apart from multi-line labels drawn through layoutWrappedText(), these are
+// the only drawing primitives the HUD touches, so the HUD's layout logic
+// doesn't need to know about programs/samplers/stores.
+static void hudFillRect(float x, float y, float w, float h, float r, float g, float b, float a) {
+    rsgBindProgramFragment(gProgFragmentColor);
+    rsgProgramFragmentConstantColor(gProgFragmentColor, r, g, b, a);
+    rsgDrawRect(x, y, x + w, y + h, 0);
+}
+
+static void hudBlitTex(rs_allocation tex, float x, float y, float w, float h) {
+    drawOffscreenResultTex(tex, (int)x, (int)y, (int)w, (int)h);
+}
+
+static void hudDrawText(const char *text, int x, int y) {
+    rsgDrawText(text, x, y);
+}
+
+static int hudMeasureTextRect(const char *text, int rectWidth, int rectHeight) {
+    return layoutWrappedText(text, 0, 0, rectWidth, rectHeight, false);
+}
+
+#define HUD_MAX_ROWS 10
+#define HUD_ROW_HEIGHT 14
+#define HUD_BAR_MAX_WIDTH 150.0f
+// 60fps reference frame time; bars are scaled relative to this so a mode
+// at 16ms/frame fills the bar and slower modes show a shorter one.
+#define HUD_REFERENCE_FRAME_MS 16.0f
+
+// Lists each mode completed so far in the current sweep by name, with a
+// colored bar whose length is scaled from its mean frame time (the frame time
+// itself is not printed as text), redrawn every frame over the result thumbnail so
+// a developer can watch the sweep progress or see a drilled-into mode live.
+static void drawHud() {
+    rsgBindFont(gFontSans);
+
+    int completed = gSweepCursor;
+    int rows = (completed < HUD_MAX_ROWS) ? completed : HUD_MAX_ROWS;
+    int startRow = completed - rows;
+
+    float labelX = 4.0f + HUD_BAR_MAX_WIDTH + 6.0f;
+    int labelWidth = rsgGetWidth() - (int)labelX - 4;
+    if (labelWidth < 0) {
+        labelWidth = 0;
+    }
+
+    float y = 4.0f;
+    for (int row = 0; row < rows; row ++) {
+        int idx = getModeAt(startRow + row);
+        float mean = gResultBuffer[idx].mean;
+
+        float barWidth = (mean > 0.0f) ? (HUD_REFERENCE_FRAME_MS / mean) * HUD_BAR_MAX_WIDTH : 0.0f;
+        if (barWidth > HUD_BAR_MAX_WIDTH) {
+            barWidth = HUD_BAR_MAX_WIDTH;
+        }
+        if (barWidth < 2.0f) {
+            barWidth = 2.0f;
+        }
+
+        // A name might wrap to more than one line; size the row to match so
+        // the bar and the next row's text never overlap it.
+        int textHeight = hudMeasureTextRect(testNames[idx], labelWidth, 0);
+        float rowHeight = (textHeight > HUD_ROW_HEIGHT) ? (float)textHeight : (float)HUD_ROW_HEIGHT;
+
+        hudFillRect(4.0f, y, barWidth, rowHeight - 2.0f, 0.3f, 0.8f, 0.3f, 0.8f);
+        rsgFontColor(1.0f, 1.0f, 1.0f, 1.0f);
+        if (textHeight > HUD_ROW_HEIGHT) {
+            layoutWrappedText(testNames[idx], (int)labelX, (int)y, labelWidth, textHeight, true);
+        } else {
+            hudDrawText(testNames[idx], (int)labelX, (int)(y + rowHeight - 4.0f));
+        }
+
+        y += rowHeight;
+    }
+
+    // Small live preview of the current mode's offscreen target, pinned to
+    // the top-right corner so it doesn't compete with the row list above.
+    // Skipped when that target isn't safely sampleable (see
+    // gActiveColorTargetCompositable).
+    if (gActiveColorTargetCompositable) {
+        hudBlitTex(gActiveColorTarget, (float)(rsgGetWidth() - 68), 4.0f, 64.0f, 64.0f);
+    }
+}
+
 int root(void) {
 
     gRenderSurfaceW = rsgGetWidth();
@@ -778,12 +1500,42 @@ int root(void) {
 
     gDt = 1.0f / 60.0f;
 
+    int benchMode = (gManualMode >= 0) ? gManualMode : getModeAt(gSweepCursor);
+
+    rsgFinish();
+
+    // Per-frame samples, gathered instead of a single start/end average.
+    // Insertion-sorted at the end of the mode to estimate percentiles and
+    // to trim outliers before computing the reported mean/stddev.
+    int64_t n = 0;
+    float frameMin = 1e9f;
+    float frameMax = 0.0f;
+    float sampleRing[PERCENTILE_RING_SIZE];
+
+    if (gCalibratedLoops[benchMode] == 0) {
+        gCalibratedLoops[benchMode] = calibrateLoopCount(benchMode);
+    }
+
+    // Warm up caches, shaders and the offscreen target before timing. These
+    // iterations are deliberately not included in any of the stats below.
+    for (int w = 0; w < gWarmupFrames; w ++) {
+        setupOffscreenTarget();
+        gRenderSurfaceW = rsAllocationGetDimX(gRenderBufferColor);
+        gRenderSurfaceH = rsAllocationGetDimY(gRenderBufferColor);
+        rsgClearColor(0.1f, 0.1f, 0.1f, 1.0f);
+        rsgClearDepth(1.0f);
+        runTest(benchMode);
+        rsgClearAllRenderTargets();
+        gRenderSurfaceW = rsgGetWidth();
+        gRenderSurfaceH = rsgGetHeight();
+    }
     rsgFinish();
-    int64_t start = rsUptimeMillis();
 
     int drawPos = 0;
-    int frameCount = 100;
+    int frameCount = gCalibratedLoops[benchMode];
     for(int i = 0; i < frameCount; i ++) {
+        int64_t frameStart = rsUptimeMillis();
+
         setupOffscreenTarget();
         gRenderSurfaceW = rsAllocationGetDimX(gRenderBufferColor);
         gRenderSurfaceH = rsAllocationGetDimY(gRenderBufferColor);
@@ -797,41 +1549,99 @@ int root(void) {
         int size = 8;
         // draw each frame at (8, 3/4 gRenderSurfaceH) with size
         drawOffscreenResult((drawPos+=size)%gRenderSurfaceW, (gRenderSurfaceH * 3) / 4, size, size);
-    }
 
-    rsgFinish();
+        rsgFinish();
+        int64_t frameEnd = rsUptimeMillis();
+        float frameTime = (float)(frameEnd - frameStart);
 
-    int64_t end = rsUptimeMillis();
-    float fps = (float)(frameCount) / ((float)(end - start)*0.001f);
-    rsDebug(testNames[benchMode], fps);
-    gResultBuffer[benchMode] = fps;
-    drawOffscreenResult(0, 0,
-                        gRenderSurfaceW / 2,
-                        gRenderSurfaceH / 2);
+        if (frameTime < frameMin) {
+            frameMin = frameTime;
+        }
+        if (frameTime > frameMax) {
+            frameMax = frameTime;
+        }
+        sampleRing[n % PERCENTILE_RING_SIZE] = frameTime;
+        n ++;
+    }
+
+    // Insertion-sort the collected samples to estimate percentiles and to
+    // reject the slowest gTrimFraction of frames as outliers before the
+    // reported mean/stddev are computed (a trimmed mean).
+    int ringCount = (n < PERCENTILE_RING_SIZE) ? (int)n : PERCENTILE_RING_SIZE;
+    for (int a = 1; a < ringCount; a ++) {
+        float key = sampleRing[a];
+        int b = a - 1;
+        while (b >= 0 && sampleRing[b] > key) {
+            sampleRing[b + 1] = sampleRing[b];
+            b --;
+        }
+        sampleRing[b + 1] = key;
+    }
+    int p50Idx = (int)(0.50f * (float)(ringCount - 1));
+    int p90Idx = (int)(0.90f * (float)(ringCount - 1));
+    int p99Idx = (int)(0.99f * (float)(ringCount - 1));
+
+    int trimCount = (int)(gTrimFraction * (float)ringCount);
+    int trimmedCount = ringCount - trimCount;
+    if (trimmedCount < 1) {
+        trimmedCount = 1;
+    }
+    float trimmedSum = 0.0f;
+    float trimmedSumSq = 0.0f;
+    for (int t = 0; t < trimmedCount; t ++) {
+        trimmedSum += sampleRing[t];
+        trimmedSumSq += sampleRing[t] * sampleRing[t];
+    }
+    float mean = trimmedSum / (float)trimmedCount;
+    float variance = trimmedSumSq / (float)trimmedCount - mean * mean;
+    float stddev = sqrt(variance > 0.0f ? variance : 0.0f);
+
+    rsDebug(testNames[benchMode], mean);
+    gResultBuffer[benchMode].mean = mean;
+    gResultBuffer[benchMode].stddev = stddev;
+    gResultBuffer[benchMode].min = frameMin;
+    gResultBuffer[benchMode].max = frameMax;
+    gResultBuffer[benchMode].p50 = sampleRing[p50Idx];
+    gResultBuffer[benchMode].p90 = sampleRing[p90Idx];
+    gResultBuffer[benchMode].p99 = sampleRing[p99Idx];
+    if (gActiveColorTargetCompositable) {
+        drawOffscreenResultScaled(gActiveColorTarget, 0, 0,
+                            gRenderSurfaceW / 2,
+                            gRenderSurfaceH / 2);
+    }
     const char* text = testNames[benchMode];
-    int left = 0, right = 0, top = 0, bottom = 0;
     uint width = rsgGetWidth();
     uint height = rsgGetHeight();
-    rsgFontColor(0.9f, 0.9f, 0.95f, 1.0f);
-    rsgBindFont(gFontSerif);
-    rsgMeasureText(text, &left, &right, &top, &bottom);
     rsgFontColor(1.0f, 1.0f, 1.0f, 1.0f);
-    rsgDrawText(text, 2 -left, height - 2 + bottom);
-
-    benchMode ++;
+    rsgBindFont(gFontSerif);
+    // Wrap the mode name across the bottom of the screen instead of a single
+    // line, so long names stay legible instead of running off-screen.
+    layoutWrappedText(text, 2, height - 40, width - 4, 40, true);
 
     gTorusRotation = 0;
 
-    if (benchMode == gMaxModes) {
-        rsSendToClientBlocking(RS_MSG_RESULTS_READY, gResultBuffer, gMaxModes*sizeof(float));
-        benchMode = 0;
-        runningLoops++;
-        if ((gMaxLoops > 0) && (runningLoops > gMaxLoops) && !sendMsgFlag) {
-            //Notifiy the test to stop and get results
-            rsDebug("gMaxLoops and runningLoops: ", gMaxLoops, runningLoops);
-            rsSendToClientBlocking(RS_MSG_TEST_DONE);
-            sendMsgFlag = true;
+    // A manually selected mode repeats indefinitely for live drill-down
+    // instead of advancing the sweep or reporting results.
+    if (gManualMode < 0) {
+        gSweepCursor ++;
+
+        if (gSweepCursor == getModeCount()) {
+            rsSendToClientBlocking(RS_MSG_RESULTS_READY, gResultBuffer, gMaxModes*sizeof(TestStats));
+            gSweepCursor = 0;
+            runningLoops++;
+            if ((gMaxLoops > 0) && (runningLoops > gMaxLoops) && !sendMsgFlag) {
+                //Notifiy the test to stop and get results
+                rsDebug("gMaxLoops and runningLoops: ", gMaxLoops, runningLoops);
+                rsSendToClientBlocking(RS_MSG_TEST_DONE);
+                sendMsgFlag = true;
+            }
         }
     }
+
+    drawHud();
+
+    if (gVsyncAligned) {
+        rsgFinish();
+    }
     return 1;
 }